@@ -0,0 +1,87 @@
+// Polls for a transaction's confirmation depth instead of assuming one freshly-mined
+// block is enough.
+//
+// `main.rs` used to mine a single block after broadcasting and assume that confirmed the
+// transaction. That doesn't hold up once a reorg is possible: the block the transaction
+// landed in can stop being part of the best chain. `wait_for_confirmations` instead
+// watches the chain tip, scans each newly connected block for the txid, and re-scans
+// from scratch if the block it previously found the transaction in falls out of the best
+// chain - reporting depth only once it has survived `min_depth` confirmations on the
+// current best chain.
+
+use bitcoincore_rpc::{Client, RpcApi};
+use serde_json::json;
+use std::error::Error;
+use std::thread::sleep;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Waits until `txid` has reached `min_depth` confirmations on the current best chain,
+/// returning the hash and height of the block it's confirmed in, plus its depth.
+pub fn wait_for_confirmations(
+    rpc: &Client,
+    txid: &str,
+    min_depth: u64,
+) -> Result<(String, u64, u64), Box<dyn Error>> {
+    // Callers mine the confirming block immediately before calling this, so the tip may
+    // already hold the transaction by the time we get here - mark the block below the tip
+    // as the last one scanned, so the scan starts at the tip itself rather than above it.
+    let scan_start = current_height(rpc)?.saturating_sub(1);
+    let mut last_scanned_height = scan_start;
+    let mut found: Option<(String, u64)> = None;
+
+    loop {
+        let tip_height = current_height(rpc)?;
+
+        if let Some((found_hash, found_height)) = found.clone() {
+            let hash_now_at_height = get_block_hash(rpc, found_height)?;
+            if hash_now_at_height != found_hash {
+                println!(
+                    "Reorg detected: block {} at height {} is no longer on the best chain, rescanning",
+                    found_hash, found_height
+                );
+                found = None;
+                last_scanned_height = scan_start;
+            }
+        }
+
+        if found.is_none() {
+            for height in (last_scanned_height + 1)..=tip_height {
+                let block_hash = get_block_hash(rpc, height)?;
+                if block_contains_tx(rpc, &block_hash, txid)? {
+                    println!(
+                        "Transaction {} found in block {} (height {})",
+                        txid, block_hash, height
+                    );
+                    found = Some((block_hash, height));
+                    break;
+                }
+            }
+            last_scanned_height = tip_height;
+        }
+
+        if let Some((block_hash, found_height)) = &found {
+            let depth = tip_height - found_height + 1;
+            if depth >= min_depth {
+                return Ok((block_hash.clone(), *found_height, depth));
+            }
+        }
+
+        sleep(POLL_INTERVAL);
+    }
+}
+
+fn current_height(rpc: &Client) -> Result<u64, Box<dyn Error>> {
+    Ok(rpc.get_blockchain_info()?.blocks)
+}
+
+fn get_block_hash(rpc: &Client, height: u64) -> Result<String, Box<dyn Error>> {
+    Ok(rpc.get_block_hash(height)?.to_string())
+}
+
+fn block_contains_tx(rpc: &Client, block_hash: &str, txid: &str) -> Result<bool, Box<dyn Error>> {
+    let block = rpc.call::<serde_json::Value>("getblock", &[json!(block_hash), json!(1)])?;
+    let txs = block["tx"].as_array().ok_or("block response missing tx list")?;
+    Ok(txs.iter().any(|tx| tx.as_str() == Some(txid)))
+}
@@ -0,0 +1,101 @@
+// Reproducible wallet creation from a BIP39 mnemonic.
+//
+// `create_or_load_wallet` makes plain legacy wallets via `create_wallet(name, None, None,
+// None, None)`, whose addresses are only recoverable from that specific wallet.dat. This
+// module instead derives a descriptor wallet from a BIP39 mnemonic, so the Miner/Trader
+// addresses can be regenerated deterministically from the same seed on any node - useful
+// for tests that need stable addresses across runs.
+
+use bitcoincore_rpc::bitcoin::address::AddressType;
+use bitcoincore_rpc::bitcoin::bip32::{DerivationPath, Xpriv};
+use bitcoincore_rpc::bitcoin::secp256k1::Secp256k1;
+use bitcoincore_rpc::bitcoin::Network;
+use bitcoincore_rpc::{Auth, Client, RpcApi};
+use serde_json::json;
+use std::error::Error;
+use std::str::FromStr;
+
+/// Generates a fresh 12-word BIP39 mnemonic.
+pub fn generate_mnemonic() -> Result<bip39::Mnemonic, Box<dyn Error>> {
+    Ok(bip39::Mnemonic::generate(12)?)
+}
+
+// Creates a blank descriptor wallet named `name` and imports wpkh/tr descriptors derived
+// from `mnemonic`, so its Miner/Trader addresses are reproducible from the seed alone.
+#[allow(clippy::too_many_arguments)]
+pub fn create_descriptor_wallet(
+    rpc: &Client,
+    rpc_url: &str,
+    rpc_user: &str,
+    rpc_pass: &str,
+    name: &str,
+    mnemonic: &bip39::Mnemonic,
+    address_type: AddressType,
+    network: Network,
+) -> Result<(), Box<dyn Error>> {
+    let seed = mnemonic.to_seed("");
+    let secp = Secp256k1::new();
+    let master = Xpriv::new_master(network, &seed)?;
+
+    let coin_type = if network == Network::Bitcoin { 0 } else { 1 };
+    let (kind, purpose) = match address_type {
+        AddressType::P2wpkh => ("wpkh", 84),
+        AddressType::P2tr => ("tr", 86),
+        other => return Err(format!("unsupported descriptor address type: {:?}", other).into()),
+    };
+    let account_path = DerivationPath::from_str(&format!("m/{}'/{}'/0'", purpose, coin_type))?;
+    let account_xprv = master.derive_priv(&secp, &account_path)?;
+    let fingerprint = master.fingerprint(&secp);
+    let origin = format!(
+        "{}/{}",
+        fingerprint,
+        account_path.to_string().trim_start_matches('m').trim_start_matches('/')
+    );
+
+    let external = finalize_descriptor(rpc, &format!("{}([{}]{}/0/*)", kind, origin, account_xprv))?;
+    let internal = finalize_descriptor(rpc, &format!("{}([{}]{}/1/*)", kind, origin, account_xprv))?;
+
+    let args = [
+        json!(name),
+        json!(null),  // disable_private_keys
+        json!(true),  // blank
+        json!(null),  // passphrase
+        json!(false), // avoid_reuse
+        json!(true),  // descriptors
+    ];
+    rpc.call::<serde_json::Value>("createwallet", &args)?;
+
+    let wallet_rpc = Client::new(
+        &format!("{}/wallet/{}", rpc_url, name),
+        Auth::UserPass(rpc_user.to_owned(), rpc_pass.to_owned()),
+    )?;
+    let import_args = [json!([
+        {
+            "desc": external,
+            "timestamp": "now",
+            "active": true,
+            "internal": false,
+            "range": [0, 999],
+        },
+        {
+            "desc": internal,
+            "timestamp": "now",
+            "active": true,
+            "internal": true,
+            "range": [0, 999],
+        },
+    ])];
+    wallet_rpc.call::<serde_json::Value>("importdescriptors", &import_args)?;
+
+    Ok(())
+}
+
+// Appends the correct checksum to a raw descriptor via `getdescriptorinfo`, since
+// `importdescriptors` rejects descriptors without one.
+fn finalize_descriptor(rpc: &Client, descriptor: &str) -> Result<String, Box<dyn Error>> {
+    let info = rpc.call::<serde_json::Value>("getdescriptorinfo", &[json!(descriptor)])?;
+    let checksummed = info["descriptor"]
+        .as_str()
+        .ok_or("getdescriptorinfo response missing descriptor")?;
+    Ok(checksummed.to_string())
+}
@@ -0,0 +1,221 @@
+// Privacy-aware coin selection for funding a payment to `recipient`.
+//
+// `send_to_address` lets Bitcoin Core pick inputs and a change output on its own, which
+// tends to leak patterns about which UTXOs belong to the same wallet (e.g. an
+// unusually-shaped change output next to a round payment amount). This module builds the
+// funding transaction(s) by hand instead, offering two selection strategies:
+//
+// - `merge`: consolidate many small UTXOs into the target output, so the "privacy leak"
+//   is a deliberate, one-off consolidation rather than an ongoing change pattern.
+// - `branch`: split the payment across several funding transactions, each with its own
+//   similar-looking change output, so no single transaction reveals the full amount sent.
+//
+// `fund_transaction` tries `merge` first and falls back to `branch` when the available
+// UTXOs can't satisfy the target amount with a single consolidated transaction.
+
+use bitcoincore_rpc::bitcoin::{Address, Amount, Txid};
+use bitcoincore_rpc::{Client, RpcApi};
+use serde::Deserialize;
+use serde_json::json;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+// Flat fee reserved for each funding transaction we build by hand.
+const ASSUMED_FEE: Amount = Amount::from_sat(1_000);
+
+// Number of funding transactions the `branch` strategy splits the payment across.
+const BRANCH_COUNT: usize = 3;
+
+// Bitcoin Core's default dust relay threshold in satoshis, below which the node rejects
+// an output outright. Used to keep `branch_strategy` from splitting `target` into shares
+// small enough to fall under it.
+const DUST_THRESHOLD: Amount = Amount::from_sat(546);
+
+// Signals specifically that the wallet's confirmed UTXOs can't cover the target amount,
+// so `fund_transaction` can tell that apart from an RPC failure and fall back to `branch`
+// only for this reason.
+#[derive(Debug)]
+struct InsufficientFundsError {
+    needed: Amount,
+    available: Amount,
+}
+
+impl fmt::Display for InsufficientFundsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "not enough confirmed UTXOs to merge into the target amount: needed {}, available {}",
+            self.needed, self.available
+        )
+    }
+}
+
+impl Error for InsufficientFundsError {}
+
+#[derive(Deserialize)]
+struct Unspent {
+    txid: String,
+    vout: u32,
+    amount: f64,
+}
+
+#[derive(Deserialize)]
+struct FundedPsbt {
+    psbt: String,
+}
+
+#[derive(Deserialize)]
+struct ProcessedPsbt {
+    psbt: String,
+    complete: bool,
+}
+
+#[derive(Deserialize)]
+struct FinalizedPsbt {
+    hex: Option<String>,
+    complete: bool,
+}
+
+// Builds transaction(s) sending `target` to `recipient` from the wallet behind `rpc`,
+// preferring a single consolidating transaction and falling back to several smaller
+// ones when the UTXO set can't support that.
+pub fn fund_transaction(
+    rpc: &Client,
+    target: Amount,
+    recipient: &Address,
+) -> Result<Vec<Txid>, Box<dyn Error>> {
+    match merge_strategy(rpc, target, recipient) {
+        Ok(txids) => Ok(txids),
+        Err(e) if e.downcast_ref::<InsufficientFundsError>().is_some() => {
+            branch_strategy(rpc, target, recipient)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+// Consolidates many small UTXOs into a single transaction paying `recipient`, spending
+// the smallest unspent outputs first. Returns an error if the smallest UTXOs can't be
+// made to cover the target plus fee, signalling the caller to fall back to `branch`.
+fn merge_strategy(
+    rpc: &Client,
+    target: Amount,
+    recipient: &Address,
+) -> Result<Vec<Txid>, Box<dyn Error>> {
+    let mut unspent = list_unspent(rpc)?;
+    unspent.sort_by(|a, b| a.amount.partial_cmp(&b.amount).unwrap());
+
+    let needed = target + ASSUMED_FEE;
+    let mut selected = Vec::new();
+    let mut total = Amount::ZERO;
+    for utxo in unspent {
+        total += Amount::from_btc(utxo.amount)?;
+        selected.push(utxo);
+        if total >= needed {
+            break;
+        }
+    }
+    if total < needed {
+        return Err(Box::new(InsufficientFundsError {
+            needed,
+            available: total,
+        }));
+    }
+
+    let inputs: Vec<_> = selected
+        .iter()
+        .map(|u| json!({"txid": u.txid, "vout": u.vout}))
+        .collect();
+    let change = total - needed;
+    let mut outputs = json!({ recipient.to_string(): target.to_btc() });
+    if change > Amount::ZERO {
+        let change_address = rpc.get_raw_change_address(None)?.assume_checked();
+        outputs[change_address.to_string()] = json!(change.to_btc());
+    }
+
+    let txid = build_sign_and_send(rpc, &json!(inputs), &outputs)?;
+    Ok(vec![txid])
+}
+
+// Splits `target` across `BRANCH_COUNT` funding transactions, each paying a fraction of
+// the amount to `recipient` and returning its own change to the wallet, so no single
+// transaction's change output stands out as covering the whole payment.
+fn branch_strategy(
+    rpc: &Client,
+    target: Amount,
+    recipient: &Address,
+) -> Result<Vec<Txid>, Box<dyn Error>> {
+    // Splitting into BRANCH_COUNT shares would make one dust for a small enough target;
+    // shrink the branch count to fit rather than let the node reject a dust output.
+    let branch_count = (BRANCH_COUNT as u64)
+        .min(target.to_sat() / DUST_THRESHOLD.to_sat())
+        .max(1) as usize;
+
+    let share = Amount::from_sat(target.to_sat() / branch_count as u64);
+    let remainder = Amount::from_sat(target.to_sat() % branch_count as u64);
+
+    let mut txids = Vec::with_capacity(branch_count);
+    for i in 0..branch_count {
+        let branch_amount = if i == 0 { share + remainder } else { share };
+        let outputs = json!({ recipient.to_string(): branch_amount.to_btc() });
+        let txid = fund_via_psbt(rpc, &outputs)?;
+        txids.push(txid);
+    }
+    Ok(txids)
+}
+
+// Lists confirmed unspent outputs for the wallet behind `rpc`.
+fn list_unspent(rpc: &Client) -> bitcoincore_rpc::Result<Vec<Unspent>> {
+    let args = [json!(1), json!(9_999_999), json!(Vec::<String>::new())];
+    rpc.call::<Vec<Unspent>>("listunspent", &args)
+}
+
+// Builds a raw transaction from explicit `inputs`, signs it with the wallet, and
+// broadcasts it.
+fn build_sign_and_send(
+    rpc: &Client,
+    inputs: &serde_json::Value,
+    outputs: &serde_json::Value,
+) -> Result<Txid, Box<dyn Error>> {
+    let raw_tx_hex =
+        rpc.call::<String>("createrawtransaction", &[inputs.clone(), outputs.clone()])?;
+
+    #[derive(Deserialize)]
+    struct SignedTransaction {
+        hex: String,
+        complete: bool,
+    }
+    let signed =
+        rpc.call::<SignedTransaction>("signrawtransactionwithwallet", &[json!(raw_tx_hex)])?;
+    if !signed.complete {
+        return Err("wallet could not fully sign the funding transaction".into());
+    }
+
+    let txid = rpc.call::<String>("sendrawtransaction", &[json!(signed.hex)])?;
+    Ok(Txid::from_str(&txid)?)
+}
+
+// Lets the wallet select inputs for `outputs` via `walletcreatefundedpsbt`, then signs,
+// finalizes, and broadcasts the resulting PSBT.
+fn fund_via_psbt(rpc: &Client, outputs: &serde_json::Value) -> Result<Txid, Box<dyn Error>> {
+    let funded = rpc.call::<FundedPsbt>(
+        "walletcreatefundedpsbt",
+        &[json!(Vec::<serde_json::Value>::new()), outputs.clone()],
+    )?;
+
+    let processed = rpc.call::<ProcessedPsbt>("walletprocesspsbt", &[json!(funded.psbt)])?;
+    if !processed.complete {
+        return Err("wallet could not fully sign the funding PSBT".into());
+    }
+
+    let finalized = rpc.call::<FinalizedPsbt>("finalizepsbt", &[json!(processed.psbt)])?;
+    if !finalized.complete {
+        return Err("PSBT finalization did not complete".into());
+    }
+    let hex = finalized
+        .hex
+        .ok_or("finalized PSBT missing raw hex")?;
+
+    let txid = rpc.call::<String>("sendrawtransaction", &[json!(hex)])?;
+    Ok(Txid::from_str(&txid)?)
+}
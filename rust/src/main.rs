@@ -1,37 +1,235 @@
 #![allow(unused)]
+mod confirmation_tracker;
+mod descriptor_wallet;
+mod funding_tx;
+// Lightweight alternative to `bitcoincore_rpc`, selectable via the `minimal-rpc` feature.
+mod rpc_client;
+
 use bitcoincore_rpc::bitcoin::{Address, Amount};
+use bitcoincore_rpc::bitcoin::address::AddressType as ScriptAddressType;
+use bitcoincore_rpc::json::AddressType as WalletAddressType;
 use bitcoincore_rpc::{Auth, Client, RpcApi};
+use clap::{Parser, Subcommand, ValueEnum};
 use serde::Deserialize;
 use serde_json::json;
 use std::fs::File;
 use std::io::Write;
 use std::str::FromStr;
 
-// Node access params
+// Node access params (used as defaults for the CLI flags below)
 const RPC_URL: &str = "http://127.0.0.1:18443"; // Default regtest RPC port
 const RPC_USER: &str = "alice";
 const RPC_PASS: &str = "password";
 
-// You can use calls not provided in RPC lib API using the generic `call` function.
-// An example of using the `send` RPC call, which doesn't have exposed API.
-// You can also use serde_json `Deserialize` derivation to capture the returned json result.
-fn send(rpc: &Client, addr: &str) -> bitcoincore_rpc::Result<String> {
+/// Command-line interface for driving the Miner/Trader regtest flow.
+#[derive(Parser)]
+#[command(name = "rust-capstone-project", about = "Miner/Trader regtest demo")]
+struct Cli {
+    /// RPC endpoint of the Bitcoin Core node
+    #[arg(long, global = true, default_value = RPC_URL)]
+    rpc_url: String,
+
+    /// RPC username
+    #[arg(long, global = true, default_value = RPC_USER)]
+    rpc_user: String,
+
+    /// RPC password
+    #[arg(long, global = true, default_value = RPC_PASS)]
+    rpc_pass: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the full end-to-end Miner/Trader flow (the original `main` behavior)
+    Run {
+        /// Address type to request for the Miner and Trader wallets
+        #[arg(long, value_enum, default_value_t = CliAddressType::Bech32)]
+        address_type: CliAddressType,
+    },
+    /// Create or load a single wallet and generate a new address for it
+    NewWallet {
+        /// Wallet name, e.g. "Miner" or "Trader"
+        name: String,
+        /// Label to attach to the generated address
+        #[arg(long, default_value = "")]
+        label: String,
+        /// Address type to request
+        #[arg(long, value_enum, default_value_t = CliAddressType::Bech32)]
+        address_type: CliAddressType,
+    },
+    /// Send an amount (in BTC) from a wallet to an address
+    Send {
+        /// Wallet name to send from
+        wallet: String,
+        /// Recipient address
+        to: String,
+        /// Amount to send, in BTC
+        amount: f64,
+    },
+    /// Fund a payment by hand-selecting coins instead of letting the node choose,
+    /// trying a consolidating transaction before falling back to several smaller ones
+    Fund {
+        /// Wallet name to fund from
+        wallet: String,
+        /// Recipient address
+        to: String,
+        /// Amount to send, in BTC
+        amount: f64,
+    },
+    /// Create a descriptor wallet seeded from a BIP39 mnemonic, so its addresses are
+    /// reproducible across runs. Generates a fresh mnemonic if `--mnemonic` is omitted.
+    NewDescriptorWallet {
+        /// Wallet name, e.g. "Miner" or "Trader"
+        name: String,
+        /// Existing BIP39 mnemonic to seed the wallet from; generates one if omitted
+        #[arg(long)]
+        mnemonic: Option<String>,
+        /// Address type to derive descriptors for (only bech32/bech32m are supported)
+        #[arg(long, value_enum, default_value_t = CliAddressType::Bech32)]
+        address_type: CliAddressType,
+    },
+}
+
+/// Address type requested on the `--address-type` flag. Converts into whichever of the
+/// two RPC-facing `AddressType` enums a given call site needs: `get_new_address` wants
+/// `bitcoincore_rpc::json::AddressType`, while descriptor derivation wants the `bitcoin`
+/// crate's `address::AddressType`.
+#[derive(Copy, Clone, ValueEnum)]
+enum CliAddressType {
+    Legacy,
+    P2shSegwit,
+    Bech32,
+    Bech32m,
+}
+
+impl From<CliAddressType> for WalletAddressType {
+    fn from(value: CliAddressType) -> Self {
+        match value {
+            CliAddressType::Legacy => WalletAddressType::Legacy,
+            CliAddressType::P2shSegwit => WalletAddressType::P2shSegwit,
+            CliAddressType::Bech32 => WalletAddressType::Bech32,
+            CliAddressType::Bech32m => WalletAddressType::Bech32m,
+        }
+    }
+}
+
+impl From<CliAddressType> for ScriptAddressType {
+    fn from(value: CliAddressType) -> Self {
+        match value {
+            CliAddressType::Legacy => ScriptAddressType::P2pkh,
+            CliAddressType::P2shSegwit => ScriptAddressType::P2sh,
+            CliAddressType::Bech32 => ScriptAddressType::P2wpkh,
+            CliAddressType::Bech32m => ScriptAddressType::P2tr,
+        }
+    }
+}
+
+// Default number of blocks we'd like the transaction to confirm within.
+const DEFAULT_CONF_TARGET: u16 = 6;
+
+// Confirmation depth `run_flow` waits for before treating the payment as settled.
+const MIN_CONFIRMATION_DEPTH: u64 = 1;
+
+// Calls `estimatesmartfee` for `conf_target` blocks and returns the feerate in sats/vB.
+// `estimatesmartfee` reports BTC/kvB, so we convert: sats/vB = BTC/kvB * 100_000.
+fn estimate_fee_rate(rpc: &Client, conf_target: u16) -> bitcoincore_rpc::Result<f64> {
+    #[derive(Deserialize)]
+    struct EstimateSmartFeeResult {
+        feerate: Option<f64>,
+        errors: Option<Vec<String>>,
+    }
+
+    let args = [json!(conf_target), json!("CONSERVATIVE")];
+    let result = rpc.call::<EstimateSmartFeeResult>("estimatesmartfee", &args)?;
+    match result.feerate {
+        Some(feerate_btc_per_kvb) => Ok(feerate_btc_per_kvb * 100_000.0),
+        // On a freshly-mined regtest chain with no fee-paying transaction history yet,
+        // estimatesmartfee reliably comes back with no feerate and an explanatory error
+        // string instead - surface that to the caller rather than panicking.
+        None => Err(bitcoincore_rpc::Error::ReturnedError(format!(
+            "estimatesmartfee returned no feerate for conf_target={}: {:?}",
+            conf_target, result.errors
+        ))),
+    }
+}
+
+// Sends `amount` to `address` at exactly `fee_rate_sat_vb`, via the raw `sendtoaddress`
+// RPC rather than the typed `send_to_address` wrapper, which has no fee-rate argument and
+// would leave the node to pick its own rate regardless of what `estimate_fee_rate` computed.
+fn send_to_address_at_fee_rate(
+    rpc: &Client,
+    address: &Address,
+    amount: Amount,
+    comment: Option<&str>,
+    fee_rate_sat_vb: f64,
+) -> Result<bitcoincore_rpc::bitcoin::Txid, Box<dyn std::error::Error>> {
     let args = [
-        json!([{addr : 100 }]), // recipient address
-        json!(null),            // conf target
-        json!(null),            // estimate mode
-        json!(null),            // fee rate in sats/vb
-        json!(null),            // Empty option object
+        json!(address.to_string()),
+        json!(amount.to_btc()),
+        json!(comment.unwrap_or("")),
+        json!(""),    // comment_to
+        json!(false), // subtractfeefromamount
+        json!(false), // replaceable
+        json!(null),  // conf_target (superseded by the explicit fee_rate below)
+        json!(null),  // estimate_mode
+        json!(null),  // avoid_reuse
+        json!(fee_rate_sat_vb),
+        json!(null), // verbose
     ];
+    let txid: String = rpc.call("sendtoaddress", &args)?;
+    Ok(bitcoincore_rpc::bitcoin::Txid::from_str(&txid)?)
+}
+
+// An example of using the `send` RPC call through whichever `RpcBackend` is active,
+// rather than `bitcoincore_rpc`'s typed API - call sites written against the trait don't
+// care whether `make_backend` built a `MinimalRpcClient` or a `bitcoincore_rpc::Client`.
+fn send(
+    backend: &dyn rpc_client::RpcBackend,
+    addr: &str,
+    conf_target: u16,
+) -> Result<String, Box<dyn std::error::Error>> {
+    use rpc_client::RpcBackend;
 
     #[derive(Deserialize)]
-    struct SendResult {
-        complete: bool,
-        txid: String,
+    struct EstimateSmartFeeResult {
+        feerate: Option<f64>,
     }
-    let send_result = rpc.call::<SendResult>("send", &args)?;
-    assert!(send_result.complete);
-    Ok(send_result.txid)
+    let estimate: EstimateSmartFeeResult = serde_json::from_value(
+        backend.call("estimatesmartfee", &[json!(conf_target), json!("CONSERVATIVE")])?,
+    )?;
+    let fee_rate = estimate
+        .feerate
+        .ok_or("estimatesmartfee returned no feerate")?
+        * 100_000.0;
+    backend.send(addr, 100.0, Some(conf_target), Some(fee_rate))
+}
+
+// Builds the RPC backend call sites talk to, selecting `MinimalRpcClient` or
+// `bitcoincore_rpc::Client` based on the `minimal-rpc` feature.
+#[cfg(feature = "minimal-rpc")]
+fn make_backend(
+    rpc_url: &str,
+    rpc_user: &str,
+    rpc_pass: &str,
+) -> Result<Box<dyn rpc_client::RpcBackend>, Box<dyn std::error::Error>> {
+    Ok(Box::new(rpc_client::MinimalRpcClient::new(
+        rpc_url, rpc_user, rpc_pass, None,
+    )))
+}
+
+#[cfg(not(feature = "minimal-rpc"))]
+fn make_backend(
+    rpc_url: &str,
+    rpc_user: &str,
+    rpc_pass: &str,
+) -> Result<Box<dyn rpc_client::RpcBackend>, Box<dyn std::error::Error>> {
+    Ok(Box::new(Client::new(
+        rpc_url,
+        Auth::UserPass(rpc_user.to_owned(), rpc_pass.to_owned()),
+    )?))
 }
 
 // Helper function to create or load a wallet
@@ -92,30 +290,212 @@ fn address_to_string(address: &Address) -> String {
     address.to_string()
 }
 
-// Helper function to get transaction details
-fn get_transaction_details(rpc: &Client, txid: &str) -> bitcoincore_rpc::Result<serde_json::Value> {
-    let args = [json!(txid), json!(true)]; // true for verbose output
-    rpc.call::<serde_json::Value>("getrawtransaction", &args)
+// Helper function to get transaction details. Goes through `RpcBackend` rather than
+// `bitcoincore_rpc::Client` directly, so it works the same whether `make_backend` built a
+// `MinimalRpcClient` or a `bitcoincore_rpc::Client`.
+fn get_transaction_details(
+    backend: &dyn rpc_client::RpcBackend,
+    txid: &str,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    use rpc_client::RpcBackend;
+    backend.getrawtransaction(txid, true)
 }
 
 // Helper function to get mempool entry
-fn get_mempool_entry(rpc: &Client, txid: &str) -> bitcoincore_rpc::Result<serde_json::Value> {
-    let args = [json!(txid)];
-    rpc.call::<serde_json::Value>("getmempoolentry", &args)
+fn get_mempool_entry(
+    backend: &dyn rpc_client::RpcBackend,
+    txid: &str,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    use rpc_client::RpcBackend;
+    backend.getmempoolentry(txid)
 }
 
 // Helper function to get block info
-fn get_block_info(rpc: &Client, block_hash: &str) -> bitcoincore_rpc::Result<serde_json::Value> {
-    let args = [json!(block_hash)];
-    rpc.call::<serde_json::Value>("getblock", &args)
+fn get_block_info(
+    backend: &dyn rpc_client::RpcBackend,
+    block_hash: &str,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    use rpc_client::RpcBackend;
+    backend.getblock(block_hash)
+}
+
+// Independently checks that each input script of `tx_details` actually satisfies the
+// scriptPubKey of the output it spends, using `bitcoinconsensus` rather than trusting
+// that the node's own mempool/block acceptance already guarantees this for us.
+fn verify_transaction(
+    backend: &dyn rpc_client::RpcBackend,
+    tx_details: &serde_json::Value,
+    txid: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use bitcoincore_rpc::bitcoin::consensus::encode::deserialize;
+    use bitcoincore_rpc::bitcoin::{OutPoint, ScriptBuf, Transaction, TxOut, Txid};
+
+    let raw_hex = tx_details["hex"]
+        .as_str()
+        .ok_or("transaction details missing raw hex")?;
+    let tx: Transaction = deserialize(&hex::decode(raw_hex)?)?;
+
+    let mut prevouts = std::collections::HashMap::new();
+    for vin in tx_details["vin"]
+        .as_array()
+        .ok_or("transaction details missing vin")?
+    {
+        let prev_txid = vin["txid"].as_str().ok_or("vin missing txid")?;
+        let prev_vout = vin["vout"].as_u64().ok_or("vin missing vout")? as u32;
+
+        let prev_tx_details = get_transaction_details(backend, prev_txid)?;
+        let prev_out = &prev_tx_details["vout"][prev_vout as usize];
+        let script_pubkey_hex = prev_out["scriptPubKey"]["hex"]
+            .as_str()
+            .ok_or("prevout missing scriptPubKey hex")?;
+        let script_pubkey = ScriptBuf::from_hex(script_pubkey_hex)?;
+        let value = prev_out["value"]
+            .as_f64()
+            .ok_or("prevout missing value")?;
+
+        prevouts.insert(
+            OutPoint::new(Txid::from_str(prev_txid)?, prev_vout),
+            TxOut {
+                value: Amount::from_btc(value)?,
+                script_pubkey,
+            },
+        );
+    }
+
+    tx.verify(|outpoint| prevouts.get(outpoint).cloned())
+        .map_err(|e| format!("transaction {} failed consensus verification: {:?}", txid, e))?;
+
+    Ok(())
 }
 
-fn main() -> bitcoincore_rpc::Result<()> {
-    // Connect to Bitcoin Core RPC
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
     let rpc = Client::new(
-        RPC_URL,
-        Auth::UserPass(RPC_USER.to_owned(), RPC_PASS.to_owned()),
+        &cli.rpc_url,
+        Auth::UserPass(cli.rpc_user.clone(), cli.rpc_pass.clone()),
+    )?;
+
+    match &cli.command {
+        Command::Run { address_type } => run_flow(&rpc, &cli, (*address_type).into()),
+        Command::NewWallet {
+            name,
+            label,
+            address_type,
+        } => new_wallet_flow(&rpc, &cli, name, label, (*address_type).into()),
+        Command::Send { wallet, to, amount } => send_flow(&rpc, &cli, wallet, to, *amount),
+        Command::Fund { wallet, to, amount } => fund_flow(&rpc, &cli, wallet, to, *amount),
+        Command::NewDescriptorWallet {
+            name,
+            mnemonic,
+            address_type,
+        } => new_descriptor_wallet_flow(&rpc, &cli, name, mnemonic.as_deref(), (*address_type).into()),
+    }
+}
+
+// Creates/loads a single wallet and prints a freshly generated address for it.
+fn new_wallet_flow(
+    rpc: &Client,
+    cli: &Cli,
+    name: &str,
+    label: &str,
+    address_type: WalletAddressType,
+) -> Result<(), Box<dyn std::error::Error>> {
+    create_or_load_wallet(rpc, name)?;
+    let wallet_rpc = Client::new(
+        &format!("{}/wallet/{}", cli.rpc_url, name),
+        Auth::UserPass(cli.rpc_user.clone(), cli.rpc_pass.clone()),
+    )?;
+    let label_opt = if label.is_empty() { None } else { Some(label) };
+    let address = wallet_rpc
+        .get_new_address(label_opt, Some(address_type))?
+        .assume_checked();
+    println!("Generated address for '{}': {}", name, address_to_string(&address));
+    Ok(())
+}
+
+// Sends `amount` BTC from `wallet` to `to` at the fee rate `estimate_fee_rate` returns,
+// letting the node pick the inputs.
+fn send_flow(
+    rpc: &Client,
+    cli: &Cli,
+    wallet: &str,
+    to: &str,
+    amount: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let wallet_rpc = Client::new(
+        &format!("{}/wallet/{}", cli.rpc_url, wallet),
+        Auth::UserPass(cli.rpc_user.clone(), cli.rpc_pass.clone()),
+    )?;
+    let address = Address::from_str(to)
+        .expect("invalid address")
+        .assume_checked();
+    let send_amount = Amount::from_btc(amount)?;
+    let fee_rate = estimate_fee_rate(&wallet_rpc, DEFAULT_CONF_TARGET)?;
+    println!(
+        "Estimated fee rate for {}-block confirmation: {} sats/vB",
+        DEFAULT_CONF_TARGET, fee_rate
+    );
+    let txid = send_to_address_at_fee_rate(&wallet_rpc, &address, send_amount, None, fee_rate)?;
+    println!("Transaction sent! TXID: {}", txid);
+    Ok(())
+}
+
+// Creates a descriptor wallet seeded from `mnemonic` (generating one if absent) and
+// prints it so the caller can reuse it to regenerate the same addresses later.
+fn new_descriptor_wallet_flow(
+    rpc: &Client,
+    cli: &Cli,
+    name: &str,
+    mnemonic: Option<&str>,
+    address_type: ScriptAddressType,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mnemonic = match mnemonic {
+        Some(words) => bip39::Mnemonic::parse(words)?,
+        None => descriptor_wallet::generate_mnemonic()?,
+    };
+    println!("Mnemonic: {}", mnemonic);
+    descriptor_wallet::create_descriptor_wallet(
+        rpc,
+        &cli.rpc_url,
+        &cli.rpc_user,
+        &cli.rpc_pass,
+        name,
+        &mnemonic,
+        address_type,
+        bitcoincore_rpc::bitcoin::Network::Regtest,
+    )?;
+    println!("Descriptor wallet '{}' created.", name);
+    Ok(())
+}
+
+// Funds a payment to `to` using privacy-aware coin selection instead of `send_to_address`.
+fn fund_flow(
+    rpc: &Client,
+    cli: &Cli,
+    wallet: &str,
+    to: &str,
+    amount: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let wallet_rpc = Client::new(
+        &format!("{}/wallet/{}", cli.rpc_url, wallet),
+        Auth::UserPass(cli.rpc_user.clone(), cli.rpc_pass.clone()),
     )?;
+    let address = Address::from_str(to)?.assume_checked();
+    let send_amount = Amount::from_btc(amount)?;
+    let txids = funding_tx::fund_transaction(&wallet_rpc, send_amount, &address)?;
+    for txid in &txids {
+        println!("Funding transaction broadcast: {}", txid);
+    }
+    Ok(())
+}
+
+// Runs the original end-to-end Miner/Trader flow.
+fn run_flow(
+    rpc: &Client,
+    cli: &Cli,
+    address_type: WalletAddressType,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let backend = make_backend(&cli.rpc_url, &cli.rpc_user, &cli.rpc_pass)?;
 
     // Get blockchain info
     let blockchain_info = rpc.get_blockchain_info()?;
@@ -123,22 +503,22 @@ fn main() -> bitcoincore_rpc::Result<()> {
 
     // Create/Load the wallets, named 'Miner' and 'Trader'. Have logic to optionally create/load them if they do not exist or not loaded already.
     println!("\n=== Creating/Loading Wallets ===");
-    create_or_load_wallet(&rpc, "Miner")?;
+    create_or_load_wallet(rpc, "Miner")?;
     std::thread::sleep(std::time::Duration::from_millis(500));
-    create_or_load_wallet(&rpc, "Trader")?;
+    create_or_load_wallet(rpc, "Trader")?;
 
     // Generate spendable balances in the Miner wallet. How many blocks needs to be mined?
     println!("\n=== Generating Mining Rewards ===");
 
     // Create wallet-specific RPC client for Miner wallet
     let miner_rpc = Client::new(
-        &format!("{}/wallet/Miner", RPC_URL),
-        Auth::UserPass(RPC_USER.to_owned(), RPC_PASS.to_owned()),
+        &format!("{}/wallet/Miner", cli.rpc_url),
+        Auth::UserPass(cli.rpc_user.clone(), cli.rpc_pass.clone()),
     )?;
 
     // Generate one address with label "Mining Reward" from the Miner wallet
     let miner_address = miner_rpc
-        .get_new_address(Some("Mining Reward"), None)?
+        .get_new_address(Some("Mining Reward"), Some(address_type))?
         .assume_checked();
     println!(
         "Generated Miner address: {}",
@@ -159,7 +539,7 @@ fn main() -> bitcoincore_rpc::Result<()> {
             blocks_mined,
             address_to_string(&miner_address)
         );
-        let block_hashes = mine_blocks_to_address(&rpc, &miner_address, 1)?;
+        let block_hashes = mine_blocks_to_address(rpc, &miner_address, 1)?;
         println!("Mined block: {}", block_hashes[0]);
 
         // Check balance after mining
@@ -187,11 +567,11 @@ fn main() -> bitcoincore_rpc::Result<()> {
     // Generate a new address from Trader wallet
     println!("\n=== Setting up Trader Wallet ===");
     let trader_rpc = Client::new(
-        &format!("{}/wallet/Trader", RPC_URL),
-        Auth::UserPass(RPC_USER.to_owned(), RPC_PASS.to_owned()),
+        &format!("{}/wallet/Trader", cli.rpc_url),
+        Auth::UserPass(cli.rpc_user.clone(), cli.rpc_pass.clone()),
     )?;
     let trader_address = trader_rpc
-        .get_new_address(Some("Received"), None)?
+        .get_new_address(Some("Received"), Some(address_type))?
         .assume_checked();
     println!(
         "Generated Trader address: {}",
@@ -201,42 +581,51 @@ fn main() -> bitcoincore_rpc::Result<()> {
     // Send 20 BTC from Miner to Trader
     println!("\n=== Sending Transaction ===");
     let send_amount = Amount::from_btc(20.0)?;
-    let txid = miner_rpc.send_to_address(
+    let fee_rate = estimate_fee_rate(&miner_rpc, DEFAULT_CONF_TARGET)?;
+    println!(
+        "Estimated fee rate for {}-block confirmation: {} sats/vB",
+        DEFAULT_CONF_TARGET, fee_rate
+    );
+    let txid = send_to_address_at_fee_rate(
+        &miner_rpc,
         &trader_address,
         send_amount,
         Some("Payment to Trader"),
-        None,
-        Some(false),
-        Some(false),
-        None,
-        None,
+        fee_rate,
     )?;
     println!("Transaction sent! TXID: {}", txid);
 
     // Check transaction in mempool
     println!("\n=== Checking Mempool ===");
-    let mempool_entry = get_mempool_entry(&rpc, &txid.to_string())?;
+    let mempool_entry = get_mempool_entry(backend.as_ref(), &txid.to_string())?;
     println!(
         "Mempool entry: {}",
         serde_json::to_string_pretty(&mempool_entry)?
     );
 
-    // Mine 1 block to confirm the transaction
+    // Mine a block so the transaction has a chance to confirm, then poll the chain tip
+    // until it has genuinely reached the desired confirmation depth on the best chain.
     println!("\n=== Confirming Transaction ===");
-    let confirm_block_hashes = mine_blocks_to_address(&rpc, &miner_address, 1)?;
-    println!("Confirmation block mined: {}", confirm_block_hashes[0]);
+    mine_blocks_to_address(rpc, &miner_address, 1)?;
+    let (confirmed_block_hash, confirmed_block_height, confirmed_depth) =
+        confirmation_tracker::wait_for_confirmations(
+            rpc,
+            &txid.to_string(),
+            MIN_CONFIRMATION_DEPTH,
+        )?;
+    println!(
+        "Transaction confirmed in block {} (height {}) at depth {}",
+        confirmed_block_hash, confirmed_block_height, confirmed_depth
+    );
 
     // Extract all required transaction details
     println!("\n=== Extracting Transaction Details ===");
-    let tx_details = get_transaction_details(&rpc, &txid.to_string())?;
+    let tx_details = get_transaction_details(backend.as_ref(), &txid.to_string())?;
     println!(
         "Transaction details: {}",
         serde_json::to_string_pretty(&tx_details)?
     );
 
-    // Get block info for confirmation details
-    let block_info = get_block_info(&rpc, &confirm_block_hashes[0])?;
-
     // Extract required information from transaction details
     let txid_str = tx_details["txid"].as_str().unwrap_or("");
 
@@ -245,7 +634,7 @@ fn main() -> bitcoincore_rpc::Result<()> {
     let input_vout = tx_details["vin"][0]["vout"].as_u64().unwrap_or(0);
 
     // Get the previous transaction to find the input address and amount
-    let prev_tx_details = get_transaction_details(&rpc, input_txid)?;
+    let prev_tx_details = get_transaction_details(backend.as_ref(), input_txid)?;
     let miner_input_address = prev_tx_details["vout"][input_vout as usize]["scriptPubKey"]
         ["address"]
         .as_str()
@@ -268,8 +657,14 @@ fn main() -> bitcoincore_rpc::Result<()> {
     let raw_fee = miner_input_amount - trader_output_amount - miner_change_amount;
     let transaction_fees = (raw_fee * 100_000_000.0).round() / 100_000_000.0;
 
-    let block_height = block_info["height"].as_u64().unwrap_or(0);
-    let block_hash = block_info["hash"].as_str().unwrap_or("");
+    let block_height = confirmed_block_height;
+    let block_hash = confirmed_block_hash.as_str();
+
+    // Independently verify the funding transaction's scripts before trusting the node's
+    // view of it, rather than assuming `sendtoaddress` produced something valid.
+    println!("\n=== Verifying Transaction Scripts ===");
+    verify_transaction(backend.as_ref(), &tx_details, &txid.to_string())?;
+    println!("Transaction scripts verified against their previous outputs.");
 
     // Write the data to ../out.txt in the specified format given in readme.md
     println!("\n=== Writing Output to File ===");